@@ -1,10 +1,13 @@
 mod sudoku {
     // sudoku-specific machinery really deserves its own module.  I didn't want to make multiple files, though.
 
+    use rand::seq::SliceRandom;
+    use rand::Rng;
     use std::fmt;
     use std::ops::{Index, IndexMut};
+    use std::str::FromStr;
 
-    #[derive(Clone, Copy, Debug)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     pub enum Cell {
         Digit(u8),
         Empty,
@@ -72,23 +75,281 @@ mod sudoku {
             self.0 % 9
         }
 
+        // Which of the nine 3x3 boxes (numbered left-to-right, top-to-bottom) this Position falls in.
+        pub fn box_index(self) -> usize {
+            (self.row() / 3) * 3 + self.column() / 3
+        }
+
         fn value(self) -> usize {
             self.0
         }
     }
 
+    // A bitmask of which digits 1..=9 are still legal for a cell: bit `d` is set when `d` is a candidate.
+    // Bit 0 is always unused, so a "nothing left to try" mask is `0`, not `0`-with-an-asterisk.
+    const ALL_DIGITS_MASK: u16 = 0x3FE;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Difficulty {
+        Easy,
+        Medium,
+        Hard,
+        Expert,
+    }
+
+    impl Difficulty {
+        // Roughly how many clues (filled cells) a generated puzzle of this difficulty should be left
+        // with; lower means more digging, which tends to mean more deduction for the solver.
+        fn target_clue_count(self) -> usize {
+            match self {
+                Difficulty::Easy => 45,
+                Difficulty::Medium => 36,
+                Difficulty::Hard => 30,
+                Difficulty::Expert => 24,
+            }
+        }
+    }
+
     #[derive(Clone)]
-    pub struct Board(Vec<Cell>);
+    pub struct Board {
+        cells: Vec<Cell>,
+        // One bitmask per row/column/box, tracking which digits are already placed somewhere in that
+        // house.  These are what let us answer "is this digit legal here?" in O(1) instead of rescanning
+        // 20 other cells every time.
+        row_used: [u16; 9],
+        col_used: [u16; 9],
+        box_used: [u16; 9],
+    }
 
     impl Board {
         pub fn new() -> Self {
-            Board(vec![Cell::Empty; 81])
+            Board {
+                cells: vec![Cell::Empty; 81],
+                row_used: [0; 9],
+                col_used: [0; 9],
+                box_used: [0; 9],
+            }
         }
 
         pub fn reset_from(&mut self, other: &Board) {
             // This is essentially a `memcpy`
-            self.0.copy_from_slice(other.0.as_slice());
+            self.cells.copy_from_slice(other.cells.as_slice());
+            self.row_used = other.row_used;
+            self.col_used = other.col_used;
+            self.box_used = other.box_used;
+        }
+
+        // Places `digit` at `position`, keeping the row/column/box masks in sync.  `digit == 0` clears
+        // the cell, same as `Cell::set`.
+        pub fn set(&mut self, position: Position, digit: u8) -> Result<(), String> {
+            // Validate on a scratch copy before touching `self` at all, so a rejected `digit` (e.g.
+            // `> 9`) leaves the board exactly as it was instead of clearing the cell and rewriting the
+            // masks on the way to an `Err`.
+            let mut cell = self.cells[position.value()];
+            cell.set(digit)?;
+
+            self.clear(position);
+            self.cells[position.value()] = cell;
+
+            if digit != 0 {
+                let bit = 1u16 << digit;
+                self.row_used[position.row()] |= bit;
+                self.col_used[position.column()] |= bit;
+                self.box_used[position.box_index()] |= bit;
+            }
+
+            Ok(())
+        }
+
+        // Empties `position`, undoing whatever `set` did to the masks.
+        pub fn clear(&mut self, position: Position) {
+            if let Cell::Digit(digit) = self.cells[position.value()] {
+                let bit = 1u16 << digit;
+                self.row_used[position.row()] &= !bit;
+                self.col_used[position.column()] &= !bit;
+                self.box_used[position.box_index()] &= !bit;
+            }
+
+            self.cells[position.value()].clear();
+        }
+
+        // The digits still legal at `position`, as a bitmask (bit `d` set means `d` is a candidate).
+        // Meaningless -- but harmless -- to call on a filled cell.
+        //
+        // Deliberate deviation from an earlier sketch of this API, which kept a cached
+        // `cell_candidates: [u16; 81]` field updated incrementally alongside the row/column/box masks.
+        // Recomputing from those masks here is just as cheap (three `u16` ORs and a NOT) and leaves no
+        // cache to keep in sync, so there's no stale-candidates class of bug to worry about.
+        pub fn candidates(&self, position: Position) -> u16 {
+            let used = self.row_used[position.row()]
+                | self.col_used[position.column()]
+                | self.box_used[position.box_index()];
+            !used & ALL_DIGITS_MASK
+        }
+
+        // Builds a fresh puzzle of the given `Difficulty`: fill the board with a random complete,
+        // valid grid, then dig holes in a random order, backing out any hole that would leave more
+        // than one solution, until we hit the target clue count (or run out of diggable cells first).
+        pub fn generate(difficulty: Difficulty) -> Board {
+            let mut rng = rand::thread_rng();
+
+            let mut board = Board::new();
+            let filled = fill_randomly(&mut board, &mut rng);
+            debug_assert!(
+                filled,
+                "a freshly shuffled empty board should always fill completely"
+            );
+
+            let mut positions: Vec<Position> = (0..81)
+                .map(|index| Position::new(index / 9, index % 9))
+                .collect();
+            positions.shuffle(&mut rng);
+
+            let mut clue_count = 81;
+            for position in positions {
+                if clue_count <= difficulty.target_clue_count() {
+                    break;
+                }
+
+                // `positions` is a permutation of all 81 cells, so this is the only time this loop
+                // visits `position`, and the board started fully filled: the cell can't be empty yet.
+                let Cell::Digit(digit) = board[position] else {
+                    unreachable!("each Position in `positions` is visited exactly once");
+                };
+
+                board.clear(position);
+                if count_solutions(&board, 2) == 1 {
+                    clue_count -= 1;
+                } else {
+                    board.set(position, digit).unwrap(); // Removing it broke uniqueness; put it back.
+                }
+            }
+
+            board
+        }
+
+        // Exports the board using the conventional one-line Sudoku encoding: 81 characters, `1`-`9`
+        // for clues and `0` for blanks, row by row.  Round-trips through `Board::from_str`.
+        pub fn to_line_string(&self) -> String {
+            self.cells
+                .iter()
+                .map(|cell| match cell {
+                    Cell::Digit(digit) => char::from_digit(*digit as u32, 10).unwrap(),
+                    Cell::Empty => '0',
+                })
+                .collect()
+        }
+
+        // The nine cells making up row `row`, paired with their `Position`s.
+        pub fn row_cells(&self, row: usize) -> impl Iterator<Item = (Position, Cell)> + '_ {
+            (0..9).map(move |column| {
+                let position = Position::new(row, column);
+                (position, self[position])
+            })
         }
+
+        // The nine cells making up column `column`, paired with their `Position`s.
+        pub fn col_cells(&self, column: usize) -> impl Iterator<Item = (Position, Cell)> + '_ {
+            (0..9).map(move |row| {
+                let position = Position::new(row, column);
+                (position, self[position])
+            })
+        }
+
+        // The nine cells making up 3x3 box `box_index` (numbered left-to-right, top-to-bottom, same
+        // numbering as `Position::box_index`), paired with their `Position`s.
+        pub fn box_cells(&self, box_index: usize) -> impl Iterator<Item = (Position, Cell)> + '_ {
+            let first_row = (box_index / 3) * 3;
+            let first_column = (box_index % 3) * 3;
+            (0..9).map(move |offset| {
+                let position = Position::new(first_row + offset / 3, first_column + offset % 3);
+                (position, self[position])
+            })
+        }
+    }
+
+    // Minimum-remaining-values: of all the empty cells, pick the one with the fewest legal digits left.
+    // Fewer candidates means fewer branches to try, and it lets a cell with *zero* candidates short-circuit
+    // the search immediately instead of getting discovered only after we've wandered into it.
+    pub(crate) fn find_cell_to_fill(board: &Board) -> Option<(Position, u16)> {
+        let mut best: Option<(Position, u16)> = None;
+
+        for row in 0..9 {
+            for column in 0..9 {
+                let position = Position::new(row, column);
+                if !board[position].is_empty() {
+                    continue;
+                }
+
+                let candidates = board.candidates(position);
+                let is_better = match best {
+                    Some((_, best_candidates)) => {
+                        candidates.count_ones() < best_candidates.count_ones()
+                    }
+                    None => true,
+                };
+                if is_better {
+                    if candidates == 0 {
+                        // Can't do better than "no legal digits at all" -- bail out and let the caller
+                        // backtrack right away instead of scanning the rest of the board for nothing.
+                        return Some((position, candidates));
+                    }
+                    best = Some((position, candidates));
+                }
+            }
+        }
+
+        best
+    }
+
+    // Fills every empty `Cell` on `board` by backtracking like `solve_sudoku`, but trying each cell's
+    // candidate digits in a random order.  Run on an empty board, this produces a random complete,
+    // valid grid (not drawn uniformly from all possible grids) -- the same trick the `sudoku` crate's generator uses.
+    fn fill_randomly(board: &mut Board, rng: &mut impl Rng) -> bool {
+        let Some((position, candidates)) = find_cell_to_fill(board) else {
+            return true;
+        };
+
+        let mut digits: Vec<u8> = (1..=9)
+            .filter(|digit| candidates & (1 << digit) != 0)
+            .collect();
+        digits.shuffle(rng);
+
+        for digit in digits {
+            board.set(position, digit).unwrap();
+            if fill_randomly(board, rng) {
+                return true;
+            }
+            board.clear(position);
+        }
+
+        false
+    }
+
+    // Counts how many distinct complete solutions `board` has, stopping as soon as `limit` is reached.
+    // Pass `limit = 2` to cheaply test uniqueness without enumerating every solution: the result is
+    // `1` if (and only if) the puzzle has exactly one.  `board` itself is left untouched; the search
+    // runs on a scratch copy.
+    pub fn count_solutions(board: &Board, limit: usize) -> usize {
+        count_solutions_from(&mut board.clone(), limit)
+    }
+
+    fn count_solutions_from(board: &mut Board, limit: usize) -> usize {
+        let Some((position, mut candidates)) = find_cell_to_fill(board) else {
+            return 1;
+        };
+
+        let mut found = 0;
+        while candidates != 0 && found < limit {
+            let digit = candidates.trailing_zeros() as u8;
+            candidates &= candidates - 1;
+
+            board.set(position, digit).unwrap();
+            found += count_solutions_from(board, limit - found);
+            board.clear(position);
+        }
+
+        found
     }
 
     impl fmt::Display for Board {
@@ -115,19 +376,69 @@ mod sudoku {
         // If you can produce a list of `u8`s (of the right length), you can make a `Board`.
         // `0`s in that list represent empty cells.  This is a convenience for outside callers.
         fn from_iter<T: IntoIterator<Item = u8>>(iter: T) -> Self {
-            let data: Vec<Cell> = iter
+            let cells: Vec<Cell> = iter
                 .into_iter()
                 .map(|digit| Cell::new(digit).unwrap()) // Is `unwrap` the best plan here?
                 .collect();
 
             debug_assert_eq!(
-                data.len(),
+                cells.len(),
                 81,
                 "A Board must have 81 Cells.  You supplied {}.",
-                data.len()
+                cells.len()
             );
 
-            Board(data)
+            // Rebuild the row/column/box masks from the filled-in cells, same as `Board::set` would.
+            let mut board = Board {
+                cells,
+                row_used: [0; 9],
+                col_used: [0; 9],
+                box_used: [0; 9],
+            };
+            for index in 0..board.cells.len() {
+                if let Cell::Digit(digit) = board.cells[index] {
+                    let position = Position::new(index / 9, index % 9);
+                    let bit = 1u16 << digit;
+                    board.row_used[position.row()] |= bit;
+                    board.col_used[position.column()] |= bit;
+                    board.box_used[position.box_index()] |= bit;
+                }
+            }
+
+            board
+        }
+    }
+
+    impl FromStr for Board {
+        type Err = String;
+
+        // Parses the conventional one-line Sudoku encoding: 81 characters, `1`-`9` for clues and `0`
+        // or `.` for blanks.  Whitespace (including newlines) is ignored, so grid-shaped text -- not
+        // just a single 81-character line -- parses too.  Unlike `FromIterator<u8>`, this reports
+        // problems instead of panicking, since the text may come from files, stdin, or URLs rather
+        // than from a literal typed out in the source.
+        fn from_str(text: &str) -> Result<Self, Self::Err> {
+            let digits: Vec<u8> = text
+                .chars()
+                .filter(|character| !character.is_whitespace())
+                .map(|character| match character {
+                    '1'..='9' => Ok(character.to_digit(10).unwrap() as u8),
+                    '0' | '.' => Ok(0),
+                    other => Err(format!(
+                        "A Sudoku puzzle string can only contain the digits 0-9 or '.', not '{}'.",
+                        other
+                    )),
+                })
+                .collect::<Result<_, _>>()?;
+
+            if digits.len() != 81 {
+                return Err(format!(
+                    "A Sudoku puzzle string must have 81 cells (ignoring whitespace).  You supplied {}.",
+                    digits.len()
+                ));
+            }
+
+            Ok(digits.into_iter().collect())
         }
     }
 
@@ -137,21 +448,182 @@ mod sudoku {
         // If you have `b: Board` and `p: Position`, you can grab the `Cell` at that `Position` with square
         // brackets.  `b[p]`.
         fn index(&self, position: Position) -> &Self::Output {
-            &self.0[position.value()]
+            &self.cells[position.value()]
         }
     }
 
     impl IndexMut<Position> for Board {
         // Same as `Index`, above, but you start with a mutable `Board` and you get back a mutable `Cell`.
+        // Careful: going through this impl (rather than `Board::set`/`Board::clear`) bypasses the
+        // row/column/box masks, so prefer those when you can.
         fn index_mut(&mut self, position: Position) -> &mut Self::Output {
-            &mut self.0[position.value()]
+            &mut self.cells[position.value()]
+        }
+    }
+
+    // A rule a digit must satisfy to be placed at a `Position`.  `Board`'s own row/column/box masks
+    // already rule out classic Sudoku conflicts cheaply as part of producing the candidate set `Solver`
+    // iterates over in the first place, so by the time a digit reaches `is_satisfied` it has *already*
+    // passed classic uniqueness -- `Constraint` is how variants like diagonal Sudoku or Killer cages
+    // bolt on rules the masks don't know about.
+    pub trait Constraint {
+        fn is_satisfied(&self, board: &Board, digit: u8, position: Position) -> bool;
+    }
+
+    // Restates the classic row/column/box rules through the `Constraint` interface, using the
+    // `row_cells`/`col_cells`/`box_cells` iterators rather than the masks `Board` already keeps.
+    // `Solver::classic()` does *not* include these: given a digit that `Board::candidates` ever
+    // offered, they can only ever return `true`, so wiring them into the default solver would just be
+    // paying to re-derive a fact the masks already guarantee. They exist so classic uniqueness is
+    // expressible the same way as any other `Constraint` -- see the demo in `main` that builds an
+    // equivalent-behaving `Solver` out of them by hand.
+    pub struct RowUnique;
+
+    impl Constraint for RowUnique {
+        fn is_satisfied(&self, board: &Board, digit: u8, position: Position) -> bool {
+            !board
+                .row_cells(position.row())
+                .any(|(_, cell)| cell == Cell::Digit(digit))
+        }
+    }
+
+    pub struct ColumnUnique;
+
+    impl Constraint for ColumnUnique {
+        fn is_satisfied(&self, board: &Board, digit: u8, position: Position) -> bool {
+            !board
+                .col_cells(position.column())
+                .any(|(_, cell)| cell == Cell::Digit(digit))
+        }
+    }
+
+    pub struct BoxUnique;
+
+    impl Constraint for BoxUnique {
+        fn is_satisfied(&self, board: &Board, digit: u8, position: Position) -> bool {
+            !board
+                .box_cells(position.box_index())
+                .any(|(_, cell)| cell == Cell::Digit(digit))
+        }
+    }
+
+    // The rule that makes X-Sudoku X-Sudoku: both main diagonals must also contain each digit at most
+    // once, on top of the classic row/column/box rules.
+    pub struct DiagonalUnique;
+
+    impl Constraint for DiagonalUnique {
+        fn is_satisfied(&self, board: &Board, digit: u8, position: Position) -> bool {
+            let (row, column) = (position.row(), position.column());
+
+            let on_main_diagonal = row != column
+                || (0..9).all(
+                    |i| !matches!(board[Position::new(i, i)], Cell::Digit(found) if found == digit),
+                );
+            let on_anti_diagonal = row + column != 8 || (0..9).all(|i| {
+                !matches!(board[Position::new(i, 8 - i)], Cell::Digit(found) if found == digit)
+            });
+
+            on_main_diagonal && on_anti_diagonal
+        }
+    }
+
+    // A Killer Sudoku cage: its `cells` must all hold different digits that sum to exactly `target`.
+    pub struct CageSum {
+        pub cells: Vec<Position>,
+        pub target: u32,
+    }
+
+    impl Constraint for CageSum {
+        fn is_satisfied(&self, board: &Board, digit: u8, position: Position) -> bool {
+            if !self
+                .cells
+                .iter()
+                .any(|cell| cell.value() == position.value())
+            {
+                return true; // This cage doesn't cover the cell being filled; nothing to check.
+            }
+
+            let mut sum = digit as u32;
+            let mut filled_count = 1; // The cell being filled, which isn't set on `board` yet.
+            for &cell in &self.cells {
+                if cell.value() == position.value() {
+                    continue;
+                }
+                if let Cell::Digit(found) = board[cell] {
+                    if found == digit {
+                        return false; // A cage's cells must all be different, just like a house.
+                    }
+                    sum += found as u32;
+                    filled_count += 1;
+                }
+            }
+
+            if filled_count == self.cells.len() {
+                sum == self.target
+            } else {
+                sum <= self.target
+            }
+        }
+    }
+
+    // A general "fill square tiles by rules" engine: `Board`'s masks narrow down candidates the same
+    // way regardless of ruleset, and `Solver` layers arbitrary `Constraint`s on top of that.  Classic
+    // Sudoku uniqueness is *always* in effect, for every `Solver`, because it lives in those masks --
+    // `Solver::classic()` is just the empty base that variant rules like `DiagonalUnique` or `CageSum`
+    // get layered onto via `with_constraint`.
+    pub struct Solver {
+        constraints: Vec<Box<dyn Constraint>>,
+    }
+
+    impl Solver {
+        pub fn classic() -> Self {
+            Solver {
+                constraints: Vec::new(),
+            }
+        }
+
+        // Adds one more rule on top of whatever this `Solver` already enforces, e.g.
+        // `Solver::classic().with_constraint(Box::new(DiagonalUnique))` for X-Sudoku.
+        pub fn with_constraint(mut self, constraint: Box<dyn Constraint>) -> Self {
+            self.constraints.push(constraint);
+            self
+        }
+
+        // Solves `board` in place, returning whether a solution was found.
+        pub fn solve(&self, board: &mut Board) -> bool {
+            let Some((position, mut candidates)) = find_cell_to_fill(board) else {
+                return true;
+            };
+
+            while candidates != 0 {
+                let digit = candidates.trailing_zeros() as u8;
+                candidates &= candidates - 1;
+
+                if !self
+                    .constraints
+                    .iter()
+                    .all(|constraint| constraint.is_satisfied(board, digit, position))
+                {
+                    continue;
+                }
+
+                board.set(position, digit).unwrap();
+                if self.solve(board) {
+                    return true;
+                }
+                board.clear(position);
+            }
+
+            false
         }
     }
 }
 
-use parse_int;
 use std::time::{Duration, Instant};
-use sudoku::{Board, Cell, Position};
+use sudoku::{
+    find_cell_to_fill, Board, BoxUnique, CageSum, Cell, ColumnUnique, DiagonalUnique, Difficulty,
+    Position, RowUnique, Solver,
+};
 
 fn main() {
     // Testing: did our NonZeroU8 optimization give us what we wanted?
@@ -208,83 +680,127 @@ fn main() {
         elapsed,
         parse_int::format_pretty_dec(total_backtracks)
     );
-}
-
-fn solve_sudoku(board: &mut Board, mut backtrack_count: usize) -> (bool, usize) {
-    if let Some(position) = first_empty_cell(board) {
-        // If there _was_ an empty `Cell`, let's test all the possible digits in that spot.
-        for digit in 1..=9 {
-            if is_digit_valid_here(board, digit, position) {
-                board[position].set(digit).unwrap();
-                let (solved, new_backtrack_count) = solve_sudoku(board, backtrack_count);
-                if solved {
-                    return (true, new_backtrack_count);
-                }
-                backtrack_count = new_backtrack_count;
-                board[position].clear();
-            }
-        }
 
-        // We tried every possible digit in that empty `Cell` and none of them were valid;
-        // therefore, the puzzle cannot be solved.
-        (false, backtrack_count + 1)
-    } else {
-        // If there _wasn't_ an empty `Cell`, the the puzzle is solved.
-        (true, backtrack_count)
-    }
-}
-
-fn first_empty_cell(board: &Board) -> Option<Position> {
-    for row in 0..9 {
-        for column in 0..9 {
-            let position = Position::new(row, column);
-            if board[position].is_empty() {
-                return Some(position);
-            }
+    println!();
+    for difficulty in [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ] {
+        println!("A freshly generated {:?} puzzle:\n", difficulty);
+        let generated = Board::generate(difficulty);
+        println!("{}", generated);
+
+        if difficulty == Difficulty::Medium {
+            let line = generated.to_line_string();
+            println!("...as a line: {}", line);
+            let round_tripped: Board = line.parse().unwrap();
+            debug_assert_eq!(
+                round_tripped.to_line_string(),
+                line,
+                "parsing our own output should round-trip"
+            );
         }
     }
 
-    None
-}
+    println!();
+    let mut classic_demo = Board::new();
+    classic_demo.reset_from(&unsolved_board);
+    let solved_classically = Solver::classic().solve(&mut classic_demo);
+    debug_assert_eq!(
+        classic_demo.to_line_string(),
+        board.to_line_string(),
+        "Solver::classic() should find the same solution as solve_sudoku"
+    );
+    println!(
+        "Solver::classic() agrees with solve_sudoku: {}",
+        solved_classically
+    );
 
-fn is_digit_valid_here(board: &Board, digit: u8, position: Position) -> bool {
-    // This is where all those iterators I didn't write would come in handy.
+    // `RowUnique`/`ColumnUnique`/`BoxUnique` are never part of `Solver::classic()` itself -- `Board`'s
+    // masks already guarantee classic uniqueness for every `Solver` -- but wiring them in explicitly
+    // shouldn't change a thing, which is exactly what this checks.
+    let mut spelled_out_classic_demo = Board::new();
+    spelled_out_classic_demo.reset_from(&unsolved_board);
+    let solved_with_explicit_house_rules = Solver::classic()
+        .with_constraint(Box::new(RowUnique))
+        .with_constraint(Box::new(ColumnUnique))
+        .with_constraint(Box::new(BoxUnique))
+        .solve(&mut spelled_out_classic_demo);
+    debug_assert_eq!(
+        spelled_out_classic_demo.to_line_string(),
+        classic_demo.to_line_string(),
+        "RowUnique/ColumnUnique/BoxUnique should never change what Solver::classic() finds"
+    );
+    println!(
+        "Spelling out RowUnique/ColumnUnique/BoxUnique by hand still agrees: {}",
+        solved_with_explicit_house_rules
+    );
 
-    // Check row
-    let row_to_check = position.row();
-    for column in 0..9 {
-        if let Cell::Digit(found_digit) = board[Position::new(row_to_check, column)] {
-            if found_digit == digit {
-                return false;
-            }
-        }
-    }
+    let mut diagonal_demo = Board::new();
+    diagonal_demo.reset_from(&unsolved_board);
+    let solved_as_x_sudoku = Solver::classic()
+        .with_constraint(Box::new(DiagonalUnique))
+        .solve(&mut diagonal_demo);
+    println!(
+        "The same puzzle, also obeying X-Sudoku's diagonals: {}",
+        solved_as_x_sudoku
+    );
 
-    // Check column
-    let column_to_check = position.column();
-    for row in 0..9 {
-        if let Cell::Digit(found_digit) = board[Position::new(row, column_to_check)] {
-            if found_digit == digit {
-                return false;
-            }
-        }
-    }
+    // A one-cage Killer Sudoku demo: r1c1 and r1c2 are both blank in `unsolved_board`, so pin a cage
+    // over them with the target their digits actually sum to in `classic_demo`'s solution, and check
+    // `Solver` still finds that same solution with `CageSum` bolted on.
+    let cage_cells = vec![Position::new(0, 0), Position::new(0, 1)];
+    let cage_target: u32 = cage_cells
+        .iter()
+        .map(|&position| match classic_demo[position] {
+            Cell::Digit(digit) => digit as u32,
+            Cell::Empty => unreachable!("classic_demo is a fully solved board"),
+        })
+        .sum();
+
+    let mut cage_demo = Board::new();
+    cage_demo.reset_from(&unsolved_board);
+    let cage_constraint = CageSum {
+        cells: cage_cells,
+        target: cage_target,
+    };
+    let solved_with_cage = Solver::classic()
+        .with_constraint(Box::new(cage_constraint))
+        .solve(&mut cage_demo);
+    debug_assert_eq!(
+        cage_demo.to_line_string(),
+        classic_demo.to_line_string(),
+        "a cage matching classic_demo's own solution should not change what Solver finds"
+    );
+    println!(
+        "The same puzzle, also obeying a Killer cage over r1c1+r1c2 == {}: {}",
+        cage_target, solved_with_cage
+    );
+}
 
-    // Check 3x3 box
-    let first_row_to_check = position.row() - position.row() % 3;
-    let last_row_to_check = first_row_to_check + 3;
-    let first_column_to_check = position.column() - position.column() % 3;
-    let last_column_to_check = first_column_to_check + 3;
-
-    for row in first_row_to_check..last_row_to_check {
-        for column in first_column_to_check..last_column_to_check {
-            if let Cell::Digit(found_digit) = board[Position::new(row, column)] {
-                if found_digit == digit {
-                    return false;
-                }
-            }
+fn solve_sudoku(board: &mut Board, mut backtrack_count: usize) -> (bool, usize) {
+    let Some((position, mut candidates)) = find_cell_to_fill(board) else {
+        // No empty `Cell` left to fill (or to fail on); the puzzle is solved.
+        return (true, backtrack_count);
+    };
+
+    // Only walk the digits that are actually still legal here, instead of blindly trying 1..=9.
+    while candidates != 0 {
+        let digit = candidates.trailing_zeros() as u8;
+        candidates &= candidates - 1; // Clear the lowest set bit; we've dealt with it now.
+
+        board.set(position, digit).unwrap();
+        let (solved, new_backtrack_count) = solve_sudoku(board, backtrack_count);
+        if solved {
+            return (true, new_backtrack_count);
         }
+        backtrack_count = new_backtrack_count;
+        board.clear(position);
     }
 
-    true
+    // We tried every remaining candidate in that empty `Cell` (maybe zero of them) and none worked;
+    // therefore this branch cannot be completed.
+    (false, backtrack_count + 1)
 }